@@ -18,6 +18,7 @@ use crate::{
     posw::{txids_to_roots, PoswMarlin},
     BlockHeaderHash,
     BlockHeaderMetadata,
+    BlockHeaderVersion,
     MerkleRoot,
     Network,
     PedersenMerkleRoot,
@@ -30,14 +31,31 @@ use snarkvm_utilities::{FromBytes, ToBytes};
 
 use anyhow::{anyhow, Result};
 use rand::{CryptoRng, Rng};
-use serde::{Deserialize, Serialize};
 use std::{
     io::{Read, Result as IoResult, Write},
     sync::Arc,
 };
+use thiserror::Error;
+
+mod serialize;
+
+/// Errors returned when validating a block header.
+#[derive(Debug, Error)]
+pub enum BlockHeaderError {
+    #[error("The block header proof of work is invalid")]
+    ProofOfWorkFailed,
+    #[error("The block header difficulty target does not match the expected difficulty target")]
+    TargetMismatch,
+}
+
+/// The number of blocks in a difficulty retargeting window.
+pub const DIFFCHANGE_INTERVAL: u32 = 2016;
+
+/// The desired wall-clock time, in seconds, for a difficulty retargeting window to elapse.
+pub const DIFFCHANGE_TIMESPAN: i64 = 14 * 24 * 60 * 60;
 
 /// Block header.
-#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BlockHeader<N: Network> {
     /// Hash of the previous block - 32 bytes
     pub previous_block_hash: BlockHeaderHash,
@@ -47,7 +65,7 @@ pub struct BlockHeader<N: Network> {
     pub commitments_root: MerkleRoot,
     /// The Merkle root representing the ledger serial numbers - 32 bytes
     pub serial_numbers_root: MerkleRoot,
-    /// The block header metadata - 20 bytes
+    /// The block header metadata - 24 bytes
     pub metadata: BlockHeaderMetadata,
     /// Proof of Succinct Work
     pub proof: ProofOfSuccinctWork<N>,
@@ -60,6 +78,7 @@ impl<N: Network> BlockHeader<N> {
         transactions: &Transactions<T>,
         commitments_root: MerkleRoot,
         serial_numbers_root: MerkleRoot,
+        version: BlockHeaderVersion,
         timestamp: i64,
         difficulty_target: u64,
         max_nonce: u32,
@@ -80,7 +99,7 @@ impl<N: Network> BlockHeader<N> {
             transactions_root,
             commitments_root,
             serial_numbers_root,
-            metadata: BlockHeaderMetadata::new(timestamp, difficulty_target, nonce),
+            metadata: BlockHeaderMetadata::new(version, timestamp, difficulty_target, nonce),
             proof: FromBytes::read_le(&proof[..])?,
         })
     }
@@ -110,6 +129,7 @@ impl<N: Network> BlockHeader<N> {
         )?;
         let serial_numbers_root = MerkleRoot::from_element(record_serial_numbers_tree.root());
 
+        let version = BlockHeaderVersion::from_consensus(0);
         let timestamp = 0i64;
         let difficulty_target = u64::MAX;
         let max_nonce = u32::MAX;
@@ -119,6 +139,7 @@ impl<N: Network> BlockHeader<N> {
             transactions,
             commitments_root,
             serial_numbers_root,
+            version,
             timestamp,
             difficulty_target,
             max_nonce,
@@ -131,6 +152,28 @@ impl<N: Network> BlockHeader<N> {
         }
     }
 
+    /// Returns `Ok(())` if the proof of work and difficulty target are valid for this header.
+    pub fn verify(&self, expected_difficulty_target: u64) -> Result<()> {
+        // Load the PoSW parameters and verify the proof is valid for this header's nonce and transactions root.
+        let posw = PoswMarlin::load()?;
+        let is_valid = posw.verify(
+            self.metadata.nonce(),
+            self.metadata.difficulty_target(),
+            &self.transactions_root,
+            &self.proof,
+        );
+        if !is_valid {
+            return Err(BlockHeaderError::ProofOfWorkFailed.into());
+        }
+
+        // Genesis headers are exempt from the difficulty target inequality.
+        if !self.is_genesis() && self.metadata.difficulty_target() != expected_difficulty_target {
+            return Err(BlockHeaderError::TargetMismatch.into());
+        }
+
+        Ok(())
+    }
+
     /// Returns `true` if the block header is a genesis block header.
     pub fn is_genesis(&self) -> bool {
         // Ensure the timestamp in the genesis block is 0.
@@ -149,7 +192,7 @@ impl<N: Network> BlockHeader<N> {
         Ok(BlockHeaderHash(hash))
     }
 
-    /// Returns the block header size in bytes - 919 bytes.
+    /// Returns the block header size in bytes - 923 bytes.
     pub fn size() -> usize {
         BlockHeaderHash::size()
             + PedersenMerkleRoot::size()
@@ -158,6 +201,20 @@ impl<N: Network> BlockHeader<N> {
             + BlockHeaderMetadata::size()
             + ProofOfSuccinctWork::<N>::size()
     }
+
+    /// Returns the retargeted difficulty target for a window with the given start/end timestamps.
+    pub fn expected_difficulty_target(first_timestamp: i64, last_timestamp: i64, last_target: u64) -> u64 {
+        // Widen to i128 before subtracting, as `last_timestamp - first_timestamp` can overflow
+        // an i64 for adversarial or malformed timestamps.
+        let actual_timespan = (last_timestamp as i128 - first_timestamp as i128)
+            .clamp(DIFFCHANGE_TIMESPAN as i128 / 4, DIFFCHANGE_TIMESPAN as i128 * 4);
+
+        // Compute `last_target * actual_timespan / DIFFCHANGE_TIMESPAN` using widening u128
+        // arithmetic, as the intermediate product can overflow a u64.
+        let new_target = (last_target as u128 * actual_timespan as u128) / DIFFCHANGE_TIMESPAN as u128;
+
+        new_target.min(u64::MAX as u128) as u64
+    }
 }
 
 impl<N: Network> FromBytes for BlockHeader<N> {
@@ -229,6 +286,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_block_header_verify_genesis() {
+        let block_header = BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(
+            &Transactions::from(&[
+                Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap(),
+            ]),
+            &mut thread_rng(),
+        )
+        .unwrap();
+
+        // The genesis header is exempt from the difficulty target inequality, so an expected
+        // target different from its own must still pass.
+        assert!(block_header.verify(block_header.metadata.difficulty_target() - 1).is_ok());
+    }
+
+    #[test]
+    fn test_block_header_verify_target_mismatch() {
+        let transactions = Transactions::from(&[
+            Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap(),
+        ]);
+        let genesis =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+
+        let block_header = BlockHeader::<Testnet2>::new(
+            BlockHeaderHash([1u8; 32]),
+            &transactions,
+            genesis.commitments_root,
+            genesis.serial_numbers_root,
+            BlockHeaderVersion::from_consensus(0),
+            1,
+            u64::MAX,
+            u32::MAX,
+            &mut thread_rng(),
+        )
+        .unwrap();
+        assert!(!block_header.is_genesis());
+
+        let error = block_header.verify(block_header.metadata.difficulty_target() - 1).unwrap_err();
+        assert!(matches!(error.downcast_ref::<BlockHeaderError>(), Some(BlockHeaderError::TargetMismatch)));
+    }
+
+    #[test]
+    fn test_block_header_verify_proof_of_work_failed() {
+        let transactions = Transactions::from(&[
+            Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap(),
+        ]);
+        let mut block_header =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+
+        // Corrupt the proof so that it no longer verifies against the header's nonce.
+        block_header.proof = ProofOfSuccinctWork::new(&vec![0u8; ProofOfSuccinctWork::<Testnet2>::size()]);
+
+        let error = block_header.verify(block_header.metadata.difficulty_target()).unwrap_err();
+        assert!(matches!(error.downcast_ref::<BlockHeaderError>(), Some(BlockHeaderError::ProofOfWorkFailed)));
+    }
+
     #[test]
     fn test_block_header_serialization() {
         let block_header = BlockHeader::<Testnet2> {
@@ -236,12 +351,19 @@ mod tests {
             transactions_root: PedersenMerkleRoot([0u8; 32]),
             commitments_root: MerkleRoot([0u8; 32]),
             serial_numbers_root: MerkleRoot([0u8; 32]),
-            metadata: BlockHeaderMetadata::new(Utc::now().timestamp(), 0u64, 0u32),
+            metadata: BlockHeaderMetadata::new(
+                BlockHeaderVersion::from_consensus(0),
+                Utc::now().timestamp(),
+                0u64,
+                0u32,
+            ),
             proof: ProofOfSuccinctWork::new(&vec![0u8; ProofOfSuccinctWork::<Testnet2>::size()]),
         };
 
+        // Bincode is not human-readable, so this goes through `ToBytesSerializer`, which prepends
+        // an 8-byte length encoding ahead of the raw `ToBytes` encoding.
         let serialized = block_header.to_bytes_le().unwrap();
-        assert_eq!(&serialized[..], &bincode::serialize(&block_header).unwrap()[..]);
+        assert_eq!(&serialized[..], &bincode::serialize(&block_header).unwrap()[8..]);
 
         let deserialized = BlockHeader::read_le(&serialized[..]).unwrap();
         assert_eq!(deserialized, block_header);
@@ -254,7 +376,12 @@ mod tests {
             transactions_root: PedersenMerkleRoot([0u8; 32]),
             commitments_root: MerkleRoot([0u8; 32]),
             serial_numbers_root: MerkleRoot([0u8; 32]),
-            metadata: BlockHeaderMetadata::new(Utc::now().timestamp(), 0u64, 0u32),
+            metadata: BlockHeaderMetadata::new(
+                BlockHeaderVersion::from_consensus(0),
+                Utc::now().timestamp(),
+                0u64,
+                0u32,
+            ),
             proof: ProofOfSuccinctWork::new(&vec![0u8; ProofOfSuccinctWork::<Testnet2>::size()]),
         };
         assert_eq!(
@@ -262,4 +389,38 @@ mod tests {
             BlockHeader::<Testnet2>::size()
         );
     }
+
+    #[test]
+    fn test_expected_difficulty_target() {
+        // An on-time window leaves the target unchanged.
+        assert_eq!(
+            BlockHeader::<Testnet2>::expected_difficulty_target(0, DIFFCHANGE_TIMESPAN, 1_000_000),
+            1_000_000
+        );
+
+        // A window that closed out 8x too fast is clamped to a 4x decrease.
+        assert_eq!(
+            BlockHeader::<Testnet2>::expected_difficulty_target(0, DIFFCHANGE_TIMESPAN / 8, 1_000_000),
+            250_000
+        );
+
+        // A window that closed out 8x too slow is clamped to a 4x increase.
+        assert_eq!(
+            BlockHeader::<Testnet2>::expected_difficulty_target(0, DIFFCHANGE_TIMESPAN * 8, 1_000_000),
+            4_000_000
+        );
+
+        // The new target never exceeds `u64::MAX`, even given widening intermediate arithmetic.
+        assert_eq!(
+            BlockHeader::<Testnet2>::expected_difficulty_target(0, DIFFCHANGE_TIMESPAN * 4, u64::MAX),
+            u64::MAX
+        );
+
+        // Extreme timestamps whose difference overflows an i64 must not panic, and are clamped
+        // to the 4x increase bound like any other overly-slow window.
+        assert_eq!(
+            BlockHeader::<Testnet2>::expected_difficulty_target(i64::MIN, i64::MAX, 1_000_000),
+            4_000_000
+        );
+    }
 }
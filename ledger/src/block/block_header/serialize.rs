@@ -0,0 +1,125 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use super::*;
+
+use snarkvm_utilities::{DeserializeExt, FromBytesDeserializer, ToBytesSerializer};
+
+use serde::{de, ser, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+impl<N: Network> Serialize for BlockHeader<N> {
+    /// Serializes the block header to a JSON-string or buffer.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => {
+                let mut header = serializer.serialize_struct("BlockHeader", 9)?;
+                header.serialize_field(
+                    "previous_block_hash",
+                    &hex::encode(self.previous_block_hash.to_bytes_le().map_err(ser::Error::custom)?),
+                )?;
+                header.serialize_field(
+                    "transactions_root",
+                    &hex::encode(self.transactions_root.to_bytes_le().map_err(ser::Error::custom)?),
+                )?;
+                header.serialize_field(
+                    "commitments_root",
+                    &hex::encode(self.commitments_root.to_bytes_le().map_err(ser::Error::custom)?),
+                )?;
+                header.serialize_field(
+                    "serial_numbers_root",
+                    &hex::encode(self.serial_numbers_root.to_bytes_le().map_err(ser::Error::custom)?),
+                )?;
+                header.serialize_field("version", &self.metadata.version().to_consensus())?;
+                header.serialize_field("timestamp", &self.metadata.timestamp())?;
+                header.serialize_field("difficulty_target", &self.metadata.difficulty_target())?;
+                header.serialize_field("nonce", &self.metadata.nonce())?;
+                header.serialize_field("proof", &hex::encode(self.proof.to_bytes_le().map_err(ser::Error::custom)?))?;
+                header.end()
+            }
+            false => ToBytesSerializer::serialize_with_size_encoding(self, serializer),
+        }
+    }
+}
+
+impl<'de, N: Network> Deserialize<'de> for BlockHeader<N> {
+    /// Deserializes the block header from a JSON-string or buffer.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.is_human_readable() {
+            true => {
+                let mut header = serde_json::Value::deserialize(deserializer)?;
+
+                let previous_block_hash: String = DeserializeExt::take_from_value::<D>(&mut header, "previous_block_hash")?;
+                let transactions_root: String = DeserializeExt::take_from_value::<D>(&mut header, "transactions_root")?;
+                let commitments_root: String = DeserializeExt::take_from_value::<D>(&mut header, "commitments_root")?;
+                let serial_numbers_root: String =
+                    DeserializeExt::take_from_value::<D>(&mut header, "serial_numbers_root")?;
+                let version: u32 = DeserializeExt::take_from_value::<D>(&mut header, "version")?;
+                let timestamp: i64 = DeserializeExt::take_from_value::<D>(&mut header, "timestamp")?;
+                let difficulty_target: u64 = DeserializeExt::take_from_value::<D>(&mut header, "difficulty_target")?;
+                let nonce: u32 = DeserializeExt::take_from_value::<D>(&mut header, "nonce")?;
+                let proof: String = DeserializeExt::take_from_value::<D>(&mut header, "proof")?;
+
+                Ok(Self {
+                    previous_block_hash: FromBytes::read_le(&hex::decode(previous_block_hash).map_err(de::Error::custom)?[..])
+                        .map_err(de::Error::custom)?,
+                    transactions_root: FromBytes::read_le(&hex::decode(transactions_root).map_err(de::Error::custom)?[..])
+                        .map_err(de::Error::custom)?,
+                    commitments_root: FromBytes::read_le(&hex::decode(commitments_root).map_err(de::Error::custom)?[..])
+                        .map_err(de::Error::custom)?,
+                    serial_numbers_root: FromBytes::read_le(
+                        &hex::decode(serial_numbers_root).map_err(de::Error::custom)?[..],
+                    )
+                    .map_err(de::Error::custom)?,
+                    metadata: BlockHeaderMetadata::new(
+                        BlockHeaderVersion::from_consensus(version),
+                        timestamp,
+                        difficulty_target,
+                        nonce,
+                    ),
+                    proof: FromBytes::read_le(&hex::decode(proof).map_err(de::Error::custom)?[..]).map_err(de::Error::custom)?,
+                })
+            }
+            false => FromBytesDeserializer::<Self>::deserialize_with_size_encoding(deserializer, "block header"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testnet2::Testnet2;
+    use snarkvm_dpc::{testnet2::Testnet2Parameters, Transaction};
+    use snarkvm_parameters::{testnet2::Transaction1, Genesis};
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_block_header_serde_json() {
+        let block_header = BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(
+            &Transactions::from(&[
+                Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap(),
+            ]),
+            &mut thread_rng(),
+        )
+        .unwrap();
+
+        // Serialize
+        let expected_string = serde_json::to_string(&block_header).unwrap();
+        // Deserialize
+        let candidate_header: BlockHeader<Testnet2> = serde_json::from_str(&expected_string).unwrap();
+        assert_eq!(block_header, candidate_header);
+    }
+}
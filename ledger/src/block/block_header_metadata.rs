@@ -0,0 +1,179 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm_utilities::{FromBytes, ToBytes};
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Result as IoResult, Write};
+
+/// The number of low-order bits of [`BlockHeaderVersion`] reserved for soft-fork signaling.
+const VERSION_BITS: u32 = 29;
+
+/// The consensus version of a block header.
+///
+/// Mirroring rust-bitcoin's `block::Version`, the low `VERSION_BITS` bits are a bitfield that
+/// miners use to signal readiness for pending soft forks, while the remaining high bits carry
+/// the block version number.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockHeaderVersion(u32);
+
+impl BlockHeaderVersion {
+    /// Initializes a version from its raw consensus-encoded integer.
+    pub fn from_consensus(version: u32) -> Self {
+        Self(version)
+    }
+
+    /// Returns the raw consensus-encoded integer for this version.
+    pub fn to_consensus(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the block version number, excluding the low-order signaling bits.
+    pub fn block_version(self) -> u32 {
+        self.0 >> VERSION_BITS
+    }
+
+    /// Returns `true` if this version signals readiness for soft-fork bit `n`.
+    pub fn signals_bit(self, n: u32) -> bool {
+        n < VERSION_BITS && (self.0 & (1 << n)) != 0
+    }
+
+    /// Returns a copy of this version with soft-fork bit `n` set. A no-op if `n` is out of range.
+    pub fn with_signal_bit(self, n: u32) -> Self {
+        match n < VERSION_BITS {
+            true => Self(self.0 | (1 << n)),
+            false => self,
+        }
+    }
+}
+
+impl FromBytes for BlockHeaderVersion {
+    #[inline]
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        Ok(Self(u32::read_le(&mut reader)?))
+    }
+}
+
+impl ToBytes for BlockHeaderVersion {
+    #[inline]
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.0.write_le(&mut writer)
+    }
+}
+
+/// Block header metadata.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockHeaderMetadata {
+    /// The consensus version of the block header - 4 bytes
+    version: BlockHeaderVersion,
+    /// Block timestamp - 8 bytes
+    timestamp: i64,
+    /// Proof of work difficulty target - 8 bytes
+    difficulty_target: u64,
+    /// Nonce for the proof of succinct work - 4 bytes
+    nonce: u32,
+}
+
+impl BlockHeaderMetadata {
+    /// Initializes a new instance of a block header metadata.
+    pub fn new(version: BlockHeaderVersion, timestamp: i64, difficulty_target: u64, nonce: u32) -> Self {
+        Self { version, timestamp, difficulty_target, nonce }
+    }
+
+    /// Returns the version of the block header.
+    pub fn version(&self) -> BlockHeaderVersion {
+        self.version
+    }
+
+    /// Returns the block timestamp.
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Returns the difficulty target for the block.
+    pub fn difficulty_target(&self) -> u64 {
+        self.difficulty_target
+    }
+
+    /// Returns the nonce for the block.
+    pub fn nonce(&self) -> u32 {
+        self.nonce
+    }
+
+    /// Returns the size, in bytes, of the block header metadata - 24 bytes.
+    pub fn size() -> usize {
+        4 + 8 + 8 + 4
+    }
+}
+
+impl FromBytes for BlockHeaderMetadata {
+    #[inline]
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let version = BlockHeaderVersion::read_le(&mut reader)?;
+        let timestamp = i64::read_le(&mut reader)?;
+        let difficulty_target = u64::read_le(&mut reader)?;
+        let nonce = u32::read_le(&mut reader)?;
+
+        Ok(Self { version, timestamp, difficulty_target, nonce })
+    }
+}
+
+impl ToBytes for BlockHeaderMetadata {
+    #[inline]
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.version.write_le(&mut writer)?;
+        self.timestamp.write_le(&mut writer)?;
+        self.difficulty_target.write_le(&mut writer)?;
+        self.nonce.write_le(&mut writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_header_version_consensus_roundtrip() {
+        let version = BlockHeaderVersion::from_consensus(0x2000_0007);
+        assert_eq!(version.to_consensus(), 0x2000_0007);
+        assert_eq!(version.block_version(), 1);
+    }
+
+    #[test]
+    fn test_block_header_version_signals_bit() {
+        let version = BlockHeaderVersion::from_consensus(0).with_signal_bit(3);
+        assert!(version.signals_bit(3));
+        assert!(!version.signals_bit(4));
+        assert!(!version.signals_bit(VERSION_BITS));
+    }
+
+    #[test]
+    fn test_block_header_version_with_signal_bit_out_of_range_is_noop() {
+        let version = BlockHeaderVersion::from_consensus(0);
+        assert_eq!(version.with_signal_bit(VERSION_BITS), version);
+    }
+
+    #[test]
+    fn test_block_header_metadata_serialization() {
+        let metadata = BlockHeaderMetadata::new(BlockHeaderVersion::from_consensus(7), 1643831, 100, 2);
+
+        let serialized = metadata.to_bytes_le().unwrap();
+        assert_eq!(serialized.len(), BlockHeaderMetadata::size());
+
+        let deserialized = BlockHeaderMetadata::read_le(&serialized[..]).unwrap();
+        assert_eq!(deserialized, metadata);
+    }
+}
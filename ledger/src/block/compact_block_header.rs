@@ -0,0 +1,297 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the snarkVM library.
+
+// The snarkVM library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The snarkVM library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the snarkVM library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{BlockHeader, Network, Transactions, Txid};
+use snarkvm_dpc::TransactionScheme;
+use snarkvm_utilities::{FromBytes, ToBytes};
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use siphasher::sip::SipHasher24;
+use std::{
+    collections::HashMap,
+    hash::Hasher,
+    io::{Read, Result as IoResult, Write},
+};
+use thiserror::Error;
+
+/// A 6-byte BIP-152-style short transaction ID.
+pub type ShortId = [u8; 6];
+
+/// Errors returned when building or reconstructing a compact block header.
+#[derive(Debug, Error)]
+pub enum CompactBlockHeaderError {
+    #[error("Two transactions hashed to the same short ID - {0:?}")]
+    ShortIdCollision(ShortId),
+    #[error("Unable to locate a transaction for short ID {0:?} in the mempool or the prefilled list")]
+    MissingTransaction(ShortId),
+    #[error("A compact block header cannot index more than {} transactions, found {0}", u16::MAX as usize + 1)]
+    TooManyTransactions(usize),
+}
+
+/// A compact block header, which relays a block header alongside short transaction IDs instead
+/// of the full set of transactions (mirroring Bitcoin's BIP-152 compact blocks).
+///
+/// A peer that already holds most of a block's transactions in its mempool can reconstruct the
+/// full `Transactions` set locally from its short IDs, rather than requiring the full block to be
+/// relayed.
+#[derive(Clone, Debug)]
+pub struct CompactBlockHeader<N: Network> {
+    /// The full block header.
+    pub header: BlockHeader<N>,
+    /// The 6-byte short transaction ID for each transaction in the block, in block order.
+    pub short_ids: Vec<ShortId>,
+}
+
+impl<N: Network> CompactBlockHeader<N> {
+    /// Builds a compact block header from a full block header and its transactions.
+    pub fn from_block<T: TransactionScheme>(header: BlockHeader<N>, transactions: &Transactions<T>) -> Result<Self> {
+        let (key0, key1) = Self::siphash_keys(&header)?;
+
+        let txids = transactions.to_transaction_ids()?;
+        // Each transaction is addressed by its `u16` position in the block elsewhere (e.g. the
+        // `prefilled` index in `reconstruct`), so reject blocks that don't fit that range.
+        if txids.len() > u16::MAX as usize + 1 {
+            return Err(CompactBlockHeaderError::TooManyTransactions(txids.len()).into());
+        }
+
+        let mut seen = HashMap::with_capacity(txids.len());
+        let mut short_ids = Vec::with_capacity(txids.len());
+        for txid in &txids {
+            let short_id = Self::short_id(key0, key1, txid)?;
+            if seen.insert(short_id, ()).is_some() {
+                return Err(CompactBlockHeaderError::ShortIdCollision(short_id).into());
+            }
+            short_ids.push(short_id);
+        }
+
+        Ok(Self { header, short_ids })
+    }
+
+    /// Reconstructs the full `Transactions` set for this block.
+    ///
+    /// Each short ID is matched against `mempool` (the transactions the caller already holds,
+    /// paired with their transaction ID), falling back to `prefilled` (transactions supplied
+    /// directly by the sender, indexed by their position in the block) for any gaps. Returns an
+    /// error if a short ID collides within `mempool` or cannot be resolved by either source.
+    pub fn reconstruct<T: TransactionScheme + Clone>(
+        &self,
+        mempool: &[(Txid, T)],
+        prefilled: &[(u16, T)],
+    ) -> Result<Transactions<T>> {
+        // `self.short_ids` may come from an untrusted peer (via `FromBytes`), so re-check the
+        // bound that `from_block` enforces rather than letting `index as u16` below wrap.
+        if self.short_ids.len() > u16::MAX as usize + 1 {
+            return Err(CompactBlockHeaderError::TooManyTransactions(self.short_ids.len()).into());
+        }
+
+        let (key0, key1) = Self::siphash_keys(&self.header)?;
+
+        let mut mempool_by_short_id = HashMap::with_capacity(mempool.len());
+        for (txid, transaction) in mempool {
+            let short_id = Self::short_id(key0, key1, txid)?;
+            if mempool_by_short_id.insert(short_id, transaction).is_some() {
+                return Err(CompactBlockHeaderError::ShortIdCollision(short_id).into());
+            }
+        }
+        let prefilled_by_index: HashMap<u16, &T> = prefilled.iter().map(|(index, tx)| (*index, tx)).collect();
+
+        let mut transactions = Vec::with_capacity(self.short_ids.len());
+        for (index, short_id) in self.short_ids.iter().enumerate() {
+            let transaction = prefilled_by_index
+                .get(&(index as u16))
+                .or_else(|| mempool_by_short_id.get(short_id))
+                .ok_or(CompactBlockHeaderError::MissingTransaction(*short_id))?;
+            transactions.push((*transaction).clone());
+        }
+
+        Ok(Transactions::from(&transactions[..]))
+    }
+
+    /// Derives the pair of SipHash-2-4 keys for this block from the SHA-256 hash of its header,
+    /// so that the keys are unique per block and resistant to short-ID grinding.
+    fn siphash_keys(header: &BlockHeader<N>) -> Result<(u64, u64)> {
+        let digest = Sha256::digest(&header.to_bytes_le()?);
+        Ok((u64_from_le_bytes(&digest[0..8]), u64_from_le_bytes(&digest[8..16])))
+    }
+
+    /// Computes the 6-byte SipHash-2-4 short ID for a transaction ID.
+    fn short_id(key0: u64, key1: u64, txid: &Txid) -> Result<ShortId> {
+        let mut hasher = SipHasher24::new_with_keys(key0, key1);
+        hasher.write(&txid.to_bytes_le()?);
+
+        let hash = hasher.finish().to_le_bytes();
+        let mut short_id = [0u8; 6];
+        short_id.copy_from_slice(&hash[..6]);
+        Ok(short_id)
+    }
+}
+
+/// Reads the first 8 bytes of `bytes` as a little-endian `u64`.
+fn u64_from_le_bytes(bytes: &[u8]) -> u64 {
+    let mut array = [0u8; 8];
+    array.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(array)
+}
+
+impl<N: Network> FromBytes for CompactBlockHeader<N> {
+    #[inline]
+    fn read_le<R: Read>(mut reader: R) -> IoResult<Self> {
+        let header = BlockHeader::read_le(&mut reader)?;
+
+        let num_short_ids = u32::read_le(&mut reader)?;
+        let mut short_ids = Vec::with_capacity(num_short_ids as usize);
+        for _ in 0..num_short_ids {
+            short_ids.push(<[u8; 6]>::read_le(&mut reader)?);
+        }
+
+        Ok(Self { header, short_ids })
+    }
+}
+
+impl<N: Network> ToBytes for CompactBlockHeader<N> {
+    #[inline]
+    fn write_le<W: Write>(&self, mut writer: W) -> IoResult<()> {
+        self.header.write_le(&mut writer)?;
+
+        (self.short_ids.len() as u32).write_le(&mut writer)?;
+        for short_id in &self.short_ids {
+            short_id.write_le(&mut writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testnet2::Testnet2;
+    use snarkvm_dpc::{testnet2::Testnet2Parameters, Transaction};
+    use snarkvm_parameters::{
+        testnet2::{Transaction1, Transaction2},
+        Genesis,
+    };
+
+    use rand::thread_rng;
+
+    #[test]
+    fn test_compact_block_header_reconstruct() {
+        let transaction = Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap();
+        let transactions = Transactions::from(&[transaction.clone()]);
+
+        let block_header =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+
+        let compact = CompactBlockHeader::from_block(block_header, &transactions).unwrap();
+        assert_eq!(compact.short_ids.len(), 1);
+
+        // Resolve the lone transaction via the mempool.
+        let txid = transactions.to_transaction_ids().unwrap()[0].clone();
+        let mempool = vec![(txid, transaction.clone())];
+        let reconstructed = compact.reconstruct(&mempool, &[]).unwrap();
+        assert_eq!(reconstructed.0, transactions.0);
+
+        // Resolve the same transaction via the prefilled list instead.
+        let reconstructed = compact.reconstruct(&[], &[(0, transaction)]).unwrap();
+        assert_eq!(reconstructed.0, transactions.0);
+
+        // An unresolved short ID is an error.
+        assert!(compact.reconstruct(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_compact_block_header_reconstruct_multiple_transactions() {
+        let transaction_a = Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap();
+        let transaction_b = Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction2::load_bytes()).unwrap();
+        let transactions = Transactions::from(&[transaction_a.clone(), transaction_b.clone()]);
+
+        let block_header =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+
+        let compact = CompactBlockHeader::from_block(block_header, &transactions).unwrap();
+        assert_eq!(compact.short_ids.len(), 2);
+        assert_ne!(compact.short_ids[0], compact.short_ids[1]);
+
+        // Resolve the first transaction via the prefilled list and the second via the mempool.
+        let txids = transactions.to_transaction_ids().unwrap();
+        let mempool = vec![(txids[1].clone(), transaction_b.clone())];
+        let prefilled = vec![(0u16, transaction_a.clone())];
+
+        let reconstructed = compact.reconstruct(&mempool, &prefilled).unwrap();
+        assert_eq!(reconstructed.0, transactions.0);
+
+        // Missing the second transaction from both sources is an error.
+        assert!(compact.reconstruct(&[], &prefilled).is_err());
+    }
+
+    #[test]
+    fn test_compact_block_header_from_block_short_id_collision() {
+        let transaction = Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap();
+        // Duplicate transactions share a transaction ID, and therefore a short ID.
+        let transactions = Transactions::from(&[transaction.clone(), transaction.clone()]);
+
+        let block_header =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+
+        let error = CompactBlockHeader::from_block(block_header, &transactions).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<CompactBlockHeaderError>(),
+            Some(CompactBlockHeaderError::ShortIdCollision(_))
+        ));
+    }
+
+    #[test]
+    fn test_compact_block_header_reconstruct_mempool_short_id_collision() {
+        let transaction = Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap();
+        let transactions = Transactions::from(&[transaction.clone()]);
+
+        let block_header =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+        let compact = CompactBlockHeader::from_block(block_header, &transactions).unwrap();
+
+        // Two distinct mempool entries sharing the same transaction ID collide on short ID.
+        let txid = transactions.to_transaction_ids().unwrap()[0].clone();
+        let mempool = vec![(txid.clone(), transaction.clone()), (txid, transaction)];
+
+        let error = compact.reconstruct(&mempool, &[]).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<CompactBlockHeaderError>(),
+            Some(CompactBlockHeaderError::ShortIdCollision(_))
+        ));
+    }
+
+    #[test]
+    fn test_compact_block_header_serialization() {
+        let transaction = Transaction::<Testnet2Parameters>::from_bytes_le(&Transaction1::load_bytes()).unwrap();
+        let transactions = Transactions::from(&[transaction]);
+
+        let block_header =
+            BlockHeader::<Testnet2>::new_genesis::<_, Testnet2Parameters, _>(&transactions, &mut thread_rng())
+                .unwrap();
+        let compact = CompactBlockHeader::from_block(block_header, &transactions).unwrap();
+
+        let serialized = compact.to_bytes_le().unwrap();
+        let deserialized = CompactBlockHeader::<Testnet2>::read_le(&serialized[..]).unwrap();
+
+        assert_eq!(compact.header, deserialized.header);
+        assert_eq!(compact.short_ids, deserialized.short_ids);
+    }
+}